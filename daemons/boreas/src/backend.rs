@@ -0,0 +1,265 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use glob::glob;
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::info;
+
+use crate::config::BoardRegisters;
+
+const EC_IO_PATH: &str = "/sys/kernel/debug/ec/ec0/io";
+
+// Sensors consulted when sampling temperatures for the closed-loop fan curve.
+// hwmon is tried first (matched by driver name), falling back to the ACPI
+// thermal zone type for platforms that only expose that interface.
+const CPU_HWMON_NAMES: &[&str] = &["coretemp", "k10temp", "zenpower"];
+const CPU_THERMAL_ZONE_TYPES: &[&str] = &["x86_pkg_temp", "cpu-thermal"];
+const GPU_HWMON_NAMES: &[&str] = &["nouveau", "amdgpu", "nvidia"];
+const GPU_THERMAL_ZONE_TYPES: &[&str] = &["gpu-thermal"];
+
+/// Validate fan speed value is within safe range
+fn validate_fan_speed(speed: u8) -> Result<u8> {
+    if speed > 100 {
+        anyhow::bail!(
+            "Invalid fan speed: {}. Must be 0-100.",
+            speed
+        );
+    }
+    Ok(speed)
+}
+
+/// Abstraction over the hardware fan controller, so the D-Bus service depends
+/// on a trait object rather than talking to the EC file directly. This is
+/// what lets `DevFanBackend` stand in for `EcFanBackend` under `--dev`.
+#[async_trait]
+pub trait FanBackend: Send + Sync {
+    async fn initialize_manual_control(&self) -> Result<()>;
+    async fn restore_auto_control(&self) -> Result<()>;
+    async fn set_fan_speeds(&self, cpu_speed: u8, gpu_speed: u8) -> Result<()>;
+    async fn get_fan_speeds(&self) -> Result<(u8, u8)>;
+}
+
+/// Talks to the real EC via `/sys/kernel/debug/ec/ec0/io`, using the register
+/// addresses and magic control values from `BoardRegisters`.
+pub struct EcFanBackend {
+    file: Arc<Mutex<File>>,
+    registers: BoardRegisters,
+}
+
+impl EcFanBackend {
+    pub fn new(registers: BoardRegisters) -> Result<Self> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(EC_IO_PATH)
+            .context("Failed to open EC interface. Ensure ec_sys module is loaded with write_support=1")?;
+
+        Ok(Self {
+            file: Arc::new(Mutex::new(file)),
+            registers,
+        })
+    }
+
+    async fn read_register(&self, register: u64) -> Result<u8> {
+        let mut file = self.file.lock().await;
+        file.seek(SeekFrom::Start(register))?;
+        let mut buf = [0u8; 1];
+        file.read_exact(&mut buf)?;
+        Ok(buf[0])
+    }
+
+    async fn write_register(&self, register: u64, value: u8) -> Result<()> {
+        let mut file = self.file.lock().await;
+        file.seek(SeekFrom::Start(register))?;
+        file.write_all(&[value])?;
+        file.flush()?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl FanBackend for EcFanBackend {
+    async fn initialize_manual_control(&self) -> Result<()> {
+        info!("Initializing manual fan control");
+        self.write_register(self.registers.manual_control, self.registers.val_manual_control_enable).await?;
+        self.write_register(self.registers.cpu_fan_mode, self.registers.val_cpu_fan_manual).await?;
+        self.write_register(self.registers.gpu_fan_mode, self.registers.val_gpu_fan_manual).await?;
+        info!("Manual fan control enabled");
+        Ok(())
+    }
+
+    async fn restore_auto_control(&self) -> Result<()> {
+        info!("Restoring automatic fan control");
+        self.write_register(self.registers.cpu_fan_mode, self.registers.val_cpu_fan_auto).await?;
+        self.write_register(self.registers.gpu_fan_mode, self.registers.val_gpu_fan_auto).await?;
+        self.write_register(self.registers.manual_control, self.registers.val_manual_control_disable).await?;
+        info!("Automatic fan control restored");
+        Ok(())
+    }
+
+    async fn set_fan_speeds(&self, cpu_speed: u8, gpu_speed: u8) -> Result<()> {
+        let cpu = validate_fan_speed(cpu_speed)?;
+        let gpu = validate_fan_speed(gpu_speed)?;
+
+        info!("Setting fan speeds: CPU={}%, GPU={}%", cpu, gpu);
+        self.write_register(self.registers.cpu_fan_write, cpu).await?;
+        self.write_register(self.registers.gpu_fan_write, gpu).await?;
+        Ok(())
+    }
+
+    async fn get_fan_speeds(&self) -> Result<(u8, u8)> {
+        let cpu = self.read_register(self.registers.cpu_fan_read).await?;
+        let gpu = self.read_register(self.registers.gpu_fan_read).await?;
+        Ok((cpu, gpu))
+    }
+}
+
+/// Logs every register write instead of touching the EC, and reports
+/// whatever was last "written" on read. Selected via `--dev` or `BOREAS_DEV=1`;
+/// also bypasses `verify_hardware()`, so this is what lets contributors
+/// develop and run integration tests on non-Nitro machines and in CI.
+pub struct DevFanBackend {
+    last_speeds: Mutex<(u8, u8)>,
+}
+
+impl DevFanBackend {
+    pub fn new() -> Self {
+        Self {
+            last_speeds: Mutex::new((0, 0)),
+        }
+    }
+}
+
+impl Default for DevFanBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl FanBackend for DevFanBackend {
+    async fn initialize_manual_control(&self) -> Result<()> {
+        info!("[dev] would enable manual fan control");
+        Ok(())
+    }
+
+    async fn restore_auto_control(&self) -> Result<()> {
+        info!("[dev] would restore automatic fan control");
+        Ok(())
+    }
+
+    async fn set_fan_speeds(&self, cpu_speed: u8, gpu_speed: u8) -> Result<()> {
+        let cpu = validate_fan_speed(cpu_speed)?;
+        let gpu = validate_fan_speed(gpu_speed)?;
+        info!("[dev] would set fan speeds: CPU={}%, GPU={}%", cpu, gpu);
+        *self.last_speeds.lock().await = (cpu, gpu);
+        Ok(())
+    }
+
+    async fn get_fan_speeds(&self) -> Result<(u8, u8)> {
+        Ok(*self.last_speeds.lock().await)
+    }
+}
+
+/// Abstraction over a temperature source, so the fan curve loop doesn't care
+/// whether it's reading hwmon, a thermal zone, or (in `--dev`) a constant.
+pub trait Sensor: Send + Sync {
+    fn temperature_c(&self) -> Result<i32>;
+}
+
+/// Reads from hwmon (matched by driver name) falling back to ACPI thermal zones.
+pub struct HwmonSensor {
+    hwmon_names: &'static [&'static str],
+    thermal_zone_types: &'static [&'static str],
+}
+
+impl HwmonSensor {
+    pub fn cpu() -> Self {
+        Self { hwmon_names: CPU_HWMON_NAMES, thermal_zone_types: CPU_THERMAL_ZONE_TYPES }
+    }
+
+    pub fn gpu() -> Self {
+        Self { hwmon_names: GPU_HWMON_NAMES, thermal_zone_types: GPU_THERMAL_ZONE_TYPES }
+    }
+}
+
+impl Sensor for HwmonSensor {
+    fn temperature_c(&self) -> Result<i32> {
+        read_hwmon_temp_c(self.hwmon_names)
+            .or_else(|| read_thermal_zone_temp_c(self.thermal_zone_types))
+            .with_context(|| format!(
+                "No temperature sensor found matching hwmon names {:?} or thermal zone types {:?}",
+                self.hwmon_names, self.thermal_zone_types,
+            ))
+    }
+}
+
+/// Always reports a fixed temperature. Used in place of the hwmon/thermal_zone
+/// sensors under `--dev`, so the fan curve loop still runs on machines with no
+/// matching sensor.
+pub struct ConstantSensor(pub i32);
+
+impl Sensor for ConstantSensor {
+    fn temperature_c(&self) -> Result<i32> {
+        Ok(self.0)
+    }
+}
+
+/// Reads the first hwmon `name` file matching one of `driver_names` and
+/// returns the integer °C from its first `temp*_input` entry.
+fn read_hwmon_temp_c(driver_names: &[&str]) -> Option<i32> {
+    for hwmon_dir in glob("/sys/class/hwmon/hwmon*").ok()?.filter_map(Result::ok) {
+        // A candidate hwmon dir whose `name` can't be read doesn't rule out
+        // a later candidate matching, so skip it rather than aborting the
+        // whole scan.
+        let Ok(name) = fs::read_to_string(hwmon_dir.join("name")) else {
+            continue;
+        };
+        let name = name.trim();
+        if !driver_names.contains(&name) {
+            continue;
+        }
+
+        let pattern = format!("{}/temp*_input", hwmon_dir.display());
+        let Ok(entries) = glob(&pattern) else {
+            continue;
+        };
+        for temp_input in entries.filter_map(Result::ok) {
+            let Some(milli) = fs::read_to_string(&temp_input)
+                .ok()
+                .and_then(|s| s.trim().parse::<i32>().ok())
+            else {
+                continue;
+            };
+            return Some(milli / 1000);
+        }
+    }
+    None
+}
+
+/// Falls back to `/sys/class/thermal/thermal_zone*/temp` for the first zone
+/// whose `type` matches one of `zone_types`.
+fn read_thermal_zone_temp_c(zone_types: &[&str]) -> Option<i32> {
+    for zone_dir in glob("/sys/class/thermal/thermal_zone*").ok()?.filter_map(Result::ok) {
+        // As in `read_hwmon_temp_c`, a single unreadable candidate shouldn't
+        // abort the scan of the remaining zones.
+        let Ok(zone_type) = fs::read_to_string(zone_dir.join("type")) else {
+            continue;
+        };
+        let zone_type = zone_type.trim();
+        if !zone_types.contains(&zone_type) {
+            continue;
+        }
+
+        let Some(milli) = fs::read_to_string(zone_dir.join("temp"))
+            .ok()
+            .and_then(|s| s.trim().parse::<i32>().ok())
+        else {
+            continue;
+        };
+        return Some(milli / 1000);
+    }
+    None
+}
@@ -0,0 +1,188 @@
+use anyhow::Context;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::time::Duration;
+use tracing::info;
+
+/// Default location for the Boreas configuration file.
+pub const DEFAULT_CONFIG_PATH: &str = "/etc/boreas/config.toml";
+
+/// EC register addresses and control values for a single board. Overriding
+/// these via a `[models."<product name>"]` table in the config file is what
+/// makes the hardware safety lock extensible: new Acer models can be added by
+/// editing config rather than recompiling the daemon.
+#[derive(Debug, Clone, Deserialize)]
+pub struct BoardRegisters {
+    pub manual_control: u64,
+    pub gpu_fan_mode: u64,
+    pub cpu_fan_mode: u64,
+    pub cpu_fan_read: u64,
+    pub gpu_fan_read: u64,
+    pub cpu_fan_write: u64,
+    pub gpu_fan_write: u64,
+    pub val_manual_control_enable: u8,
+    pub val_cpu_fan_manual: u8,
+    pub val_gpu_fan_manual: u8,
+    pub val_manual_control_disable: u8,
+    pub val_cpu_fan_auto: u8,
+    pub val_gpu_fan_auto: u8,
+}
+
+impl Default for BoardRegisters {
+    /// Register map for the Acer Nitro AN515-57, used when no config file is
+    /// present or the config file has no `[models."Nitro AN515-57"]` table.
+    fn default() -> Self {
+        Self {
+            manual_control: 3,
+            gpu_fan_mode: 33,
+            cpu_fan_mode: 34,
+            cpu_fan_read: 19,
+            gpu_fan_read: 21,
+            cpu_fan_write: 55,
+            gpu_fan_write: 58,
+            val_manual_control_enable: 17,
+            val_cpu_fan_manual: 12,
+            val_gpu_fan_manual: 48,
+            val_manual_control_disable: 0,
+            val_cpu_fan_auto: 4,
+            val_gpu_fan_auto: 16,
+        }
+    }
+}
+
+/// A named static fan profile's CPU/GPU speed, settable via `[profiles.<name>]`.
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct ProfileSpeeds {
+    pub cpu_speed: u8,
+    pub gpu_speed: u8,
+}
+
+/// Fan curve sampling parameters, overridable via `[curve]`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct CurveSettings {
+    pub hysteresis_c: i32,
+    pub sample_interval_secs: u64,
+}
+
+impl Default for CurveSettings {
+    fn default() -> Self {
+        Self {
+            hysteresis_c: 3,
+            sample_interval_secs: 2,
+        }
+    }
+}
+
+impl CurveSettings {
+    pub fn sample_interval(&self) -> Duration {
+        Duration::from_secs(self.sample_interval_secs)
+    }
+}
+
+/// Background telemetry sampling parameters, overridable via `[telemetry]`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct TelemetrySettings {
+    pub interval_secs: u64,
+}
+
+impl Default for TelemetrySettings {
+    fn default() -> Self {
+        Self { interval_secs: 2 }
+    }
+}
+
+impl TelemetrySettings {
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval_secs)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub profiles: HashMap<String, ProfileSpeeds>,
+    pub curve: CurveSettings,
+    pub telemetry: TelemetrySettings,
+    pub models: HashMap<String, BoardRegisters>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        let mut profiles = HashMap::new();
+        profiles.insert("silent".to_string(), ProfileSpeeds { cpu_speed: 25, gpu_speed: 25 });
+        profiles.insert("balanced".to_string(), ProfileSpeeds { cpu_speed: 50, gpu_speed: 50 });
+        profiles.insert("maxpower".to_string(), ProfileSpeeds { cpu_speed: 100, gpu_speed: 100 });
+
+        let mut models = HashMap::new();
+        models.insert("Nitro AN515-57".to_string(), BoardRegisters::default());
+
+        Self {
+            profiles,
+            curve: CurveSettings::default(),
+            telemetry: TelemetrySettings::default(),
+            models,
+        }
+    }
+}
+
+impl Config {
+    /// Loads configuration from `path`, falling back to built-in defaults
+    /// when the file does not exist. A present-but-malformed file is a
+    /// startup error.
+    pub fn load(path: &str) -> anyhow::Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => {
+                info!("Loading configuration from {}", path);
+                let mut config: Config = toml::from_str(&contents)
+                    .with_context(|| format!("Failed to parse config file {}", path))?;
+                config.merge_default_models();
+                config.merge_default_profiles();
+                Ok(config)
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                info!("No config file at {}, using built-in defaults", path);
+                Ok(Config::default())
+            }
+            Err(e) => Err(e).with_context(|| format!("Failed to read config file {}", path)),
+        }
+    }
+
+    /// Adds the built-in board register maps for any model not already
+    /// defined in `self.models`. Without this, a config file that defines
+    /// even one `[models."X"]` table would replace the whole `models` map
+    /// (since `#[serde(default)]` only fills in *missing* top-level fields),
+    /// silently dropping built-in support for the Nitro AN515-57.
+    fn merge_default_models(&mut self) {
+        for (name, registers) in Config::default().models {
+            self.models.entry(name).or_insert(registers);
+        }
+    }
+
+    /// Adds the built-in `silent`/`balanced`/`maxpower` profiles for any name
+    /// not already defined in `self.profiles`. Without this, a config file
+    /// that defines even one `[profiles.<name>]` table would replace the
+    /// whole `profiles` map (since `#[serde(default)]` only fills in
+    /// *missing* top-level fields), silently dropping the built-in profiles
+    /// and leaving `FanProfile::speeds` to fall back to a hardcoded 50/50.
+    fn merge_default_profiles(&mut self) {
+        for (name, speeds) in Config::default().profiles {
+            self.profiles.entry(name).or_insert(speeds);
+        }
+    }
+
+    /// Looks up the board register map whose model key is a substring of
+    /// `product_name`. This is also the hardware safety lock's allowlist:
+    /// a board with no matching entry is unsupported.
+    pub fn board_registers(&self, product_name: &str) -> Option<&BoardRegisters> {
+        self.models
+            .iter()
+            .find(|(name, _)| product_name.contains(name.as_str()))
+            .map(|(_, registers)| registers)
+    }
+
+    pub fn supported_models(&self) -> Vec<&str> {
+        self.models.keys().map(String::as_str).collect()
+    }
+}
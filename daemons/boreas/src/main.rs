@@ -1,38 +1,33 @@
 use anyhow::{Context, Result};
-use std::fs::{self, File, OpenOptions};
-use std::io::{Read, Seek, SeekFrom, Write};
+use std::fs;
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use std::time::Duration;
+use tokio::signal::unix::{signal, SignalKind};
+use tokio::sync::{Mutex, Notify};
+use tokio::task::JoinHandle;
 use tracing::{info, warn, error};
+use zbus::object_server::SignalEmitter;
 use zbus::{interface, ConnectionBuilder};
 
-const EC_IO_PATH: &str = "/sys/kernel/debug/ec/ec0/io";
+mod backend;
+mod config;
+mod variants;
 
-// Hardware Safety Lock - Supported models
-const SUPPORTED_MODELS: &[&str] = &[
-    "Nitro AN515-57",
-];
+use backend::{ConstantSensor, DevFanBackend, EcFanBackend, FanBackend, HwmonSensor, Sensor};
+use config::{BoardRegisters, Config};
+use variants::{FanVariant, VariantStore, DEFAULT_VARIANTS_PATH, DEFAULT_VARIANT_NAME};
 
 // DMI paths for hardware identification
 const DMI_PRODUCT_NAME: &str = "/sys/class/dmi/id/product_name";
 const DMI_BOARD_VENDOR: &str = "/sys/class/dmi/id/board_vendor";
 
-// EC Register addresses for Acer Nitro AN515-57
-const REG_MANUAL_CONTROL: u64 = 3;
-const REG_GPU_FAN_MODE: u64 = 33;
-const REG_CPU_FAN_MODE: u64 = 34;
-const REG_CPU_FAN_READ: u64 = 19;
-const REG_GPU_FAN_READ: u64 = 21;
-const REG_CPU_FAN_WRITE: u64 = 55;
-const REG_GPU_FAN_WRITE: u64 = 58;
-
-// Control values
-const VAL_MANUAL_CONTROL_ENABLE: u8 = 17;
-const VAL_CPU_FAN_MANUAL: u8 = 12;
-const VAL_GPU_FAN_MANUAL: u8 = 48;
-const VAL_MANUAL_CONTROL_DISABLE: u8 = 0;
-const VAL_CPU_FAN_AUTO: u8 = 4;
-const VAL_GPU_FAN_AUTO: u8 = 16;
+/// Dry-run mode is selected with `--dev` or `BOREAS_DEV=1`. It swaps in
+/// `DevFanBackend` (which only logs what it would write) and skips
+/// `verify_hardware()`, so contributors can develop and test Boreas on
+/// non-Nitro machines and in CI.
+fn dev_mode_requested() -> bool {
+    std::env::args().any(|a| a == "--dev") || std::env::var("BOREAS_DEV").is_ok()
+}
 
 #[derive(Debug, Clone, Copy)]
 enum FanProfile {
@@ -40,30 +35,46 @@ enum FanProfile {
     Balanced,
     MaxPower,
     Auto,
+    Curve,
 }
 
 impl FanProfile {
-    fn cpu_speed(&self) -> u8 {
+    /// Config key used to look up this profile's speeds in `[profiles.<name>]`.
+    fn config_key(&self) -> &'static str {
         match self {
-            FanProfile::Silent => 25,
-            FanProfile::Balanced => 50,
-            FanProfile::MaxPower => 100,
-            FanProfile::Auto => 50, // Will be reset to auto mode
+            FanProfile::Silent => "silent",
+            FanProfile::Balanced => "balanced",
+            FanProfile::MaxPower => "maxpower",
+            FanProfile::Auto => "balanced", // Will be reset to auto mode
+            FanProfile::Curve => "balanced", // Speed is driven by the curve loop, not this table
         }
     }
 
-    fn gpu_speed(&self) -> u8 {
+    fn speeds(&self, config: &Config) -> (u8, u8) {
+        config
+            .profiles
+            .get(self.config_key())
+            .map(|p| (p.cpu_speed, p.gpu_speed))
+            .unwrap_or((50, 50))
+    }
+
+    /// Name used when persisting this profile in a saved variant. Unlike
+    /// `config_key`, `Auto` and `Curve` get their own distinct names rather
+    /// than aliasing "balanced".
+    fn variant_name(&self) -> &'static str {
         match self {
-            FanProfile::Silent => 25,
-            FanProfile::Balanced => 50,
-            FanProfile::MaxPower => 100,
-            FanProfile::Auto => 50,
+            FanProfile::Silent => "silent",
+            FanProfile::Balanced => "balanced",
+            FanProfile::MaxPower => "maxpower",
+            FanProfile::Auto => "auto",
+            FanProfile::Curve => "curve",
         }
     }
 }
 
-/// Verify hardware compatibility before allowing operation
-fn verify_hardware() -> Result<()> {
+/// Verify hardware compatibility before allowing operation. Returns the
+/// matching board register map so callers don't have to re-look it up.
+fn verify_hardware(config: &Config) -> Result<BoardRegisters> {
     info!("Performing hardware compatibility check...");
 
     // Read product name
@@ -74,13 +85,16 @@ fn verify_hardware() -> Result<()> {
 
     info!("Detected hardware: {}", product_name);
 
-    // Check if this hardware is supported
-    let is_supported = SUPPORTED_MODELS.iter().any(|model| product_name.contains(model));
+    // Check if this hardware is supported. The allowlist itself comes from
+    // `config.models`, so new boards are supported by editing config rather
+    // than recompiling.
+    let registers = config.board_registers(&product_name);
 
-    if !is_supported {
+    let Some(registers) = registers else {
+        let supported_models = config.supported_models();
         error!("HARDWARE SAFETY LOCK ENGAGED");
         error!("Detected model: {}", product_name);
-        error!("This daemon is designed ONLY for: {:?}", SUPPORTED_MODELS);
+        error!("This daemon is designed ONLY for: {:?}", supported_models);
         error!("");
         error!("Running this daemon on unsupported hardware may cause:");
         error!("  - Hardware damage");
@@ -90,103 +104,258 @@ fn verify_hardware() -> Result<()> {
         error!("");
         error!("If you believe your hardware should be supported, please:");
         error!("  1. Verify your exact model number");
-        error!("  2. Open an issue at: https://github.com/yourrepo/boreas");
+        error!("  2. Add a [models.\"<model>\"] entry to the config file");
         error!("  3. DO NOT bypass this safety check");
+        error!("  (or run with --dev / BOREAS_DEV=1 to develop against a dry-run backend)");
 
         anyhow::bail!(
             "Hardware safety check failed. Detected: '{}'. Supported: {:?}",
             product_name,
-            SUPPORTED_MODELS
+            supported_models
         );
-    }
+    };
 
     info!("✓ Hardware compatibility verified: {}", product_name);
-    Ok(())
+    Ok(registers.clone())
 }
 
-/// Validate fan speed value is within safe range
-fn validate_fan_speed(speed: u8) -> Result<u8> {
-    if speed > 100 {
-        anyhow::bail!(
-            "Invalid fan speed: {}. Must be 0-100.",
-            speed
-        );
+/// A sorted set of (temperature °C, speed %) points defining a piecewise-linear
+/// fan curve. Temperatures below the first point or above the last are clamped
+/// to that point's speed.
+#[derive(Debug, Clone)]
+struct FanCurve {
+    points: Vec<(i32, u8)>,
+}
+
+impl FanCurve {
+    fn new(mut points: Vec<(i32, u8)>) -> Result<Self> {
+        if points.is_empty() {
+            anyhow::bail!("fan curve must have at least one point");
+        }
+        points.sort_by_key(|p| p.0);
+        Ok(Self { points })
+    }
+
+    /// Interpolate the commanded speed for `temp_c`, clamping outside the curve's range.
+    fn speed_at(&self, temp_c: i32) -> u8 {
+        let first = self.points[0];
+        let last = *self.points.last().unwrap();
+
+        if temp_c <= first.0 {
+            return first.1;
+        }
+        if temp_c >= last.0 {
+            return last.1;
+        }
+
+        for pair in self.points.windows(2) {
+            let (t0, s0) = pair[0];
+            let (t1, s1) = pair[1];
+            if temp_c >= t0 && temp_c <= t1 {
+                if t1 == t0 {
+                    return s1;
+                }
+                let frac = (temp_c - t0) as f64 / (t1 - t0) as f64;
+                return (s0 as f64 + (s1 as f64 - s0 as f64) * frac).round() as u8;
+            }
+        }
+
+        last.1
     }
-    Ok(speed)
 }
 
-struct EcController {
-    file: Arc<Mutex<File>>,
+/// Per-fan hysteresis tracking: a commanded speed only decreases once the
+/// temperature has dropped at least `hysteresis_c` below the temperature
+/// recorded when the current speed was last commanded. Increases are always
+/// applied immediately, and a sample whose target equals the current speed
+/// re-commands it and records its temperature.
+struct HysteresisState {
+    last_commanded: u8,
+    last_trigger_temp: i32,
 }
 
-impl EcController {
-    fn new() -> Result<Self> {
-        let file = OpenOptions::new()
-            .read(true)
-            .write(true)
-            .open(EC_IO_PATH)
-            .context("Failed to open EC interface. Ensure ec_sys module is loaded with write_support=1")?;
-
-        Ok(Self {
-            file: Arc::new(Mutex::new(file)),
-        })
+impl HysteresisState {
+    fn new() -> Self {
+        Self {
+            last_commanded: 0,
+            last_trigger_temp: i32::MIN,
+        }
     }
 
-    async fn read_register(&self, register: u64) -> Result<u8> {
-        let mut file = self.file.lock().await;
-        file.seek(SeekFrom::Start(register))?;
-        let mut buf = [0u8; 1];
-        file.read_exact(&mut buf)?;
-        Ok(buf[0])
+    fn next(&mut self, target: u8, temp_c: i32, hysteresis_c: i32) -> u8 {
+        if target >= self.last_commanded || temp_c <= self.last_trigger_temp - hysteresis_c {
+            self.last_commanded = target;
+            self.last_trigger_temp = temp_c;
+        }
+        self.last_commanded
     }
+}
 
-    async fn write_register(&self, register: u64, value: u8) -> Result<()> {
-        let mut file = self.file.lock().await;
-        file.seek(SeekFrom::Start(register))?;
-        file.write_all(&[value])?;
-        file.flush()?;
-        Ok(())
-    }
+struct CurveConfig {
+    cpu: FanCurve,
+    gpu: FanCurve,
+    hysteresis_c: i32,
+    sample_interval: Duration,
+}
+
+/// A running fan curve control loop, along with the handle needed to cancel it.
+struct CurveTask {
+    handle: JoinHandle<()>,
+    cancel: Arc<Notify>,
+}
+
+/// Samples CPU/GPU temperatures on a fixed interval, evaluates `config`'s
+/// curves with hysteresis, and writes the result via `fans`. Exits and
+/// restores automatic fan control as soon as `cancel` is notified.
+async fn run_curve_loop(
+    fans: Arc<dyn FanBackend>,
+    cpu_sensor: Arc<dyn Sensor>,
+    gpu_sensor: Arc<dyn Sensor>,
+    config: CurveConfig,
+    cancel: Arc<Notify>,
+) {
+    let mut cpu_state = HysteresisState::new();
+    let mut gpu_state = HysteresisState::new();
+
+    loop {
+        tokio::select! {
+            _ = cancel.notified() => break,
+            _ = tokio::time::sleep(config.sample_interval) => {}
+        }
+
+        let cpu_temp = match cpu_sensor.temperature_c() {
+            Ok(t) => t,
+            Err(e) => {
+                warn!("Fan curve: failed to read CPU temperature: {}", e);
+                continue;
+            }
+        };
+        let gpu_temp = match gpu_sensor.temperature_c() {
+            Ok(t) => t,
+            Err(e) => {
+                warn!("Fan curve: failed to read GPU temperature: {}", e);
+                continue;
+            }
+        };
 
-    async fn initialize_manual_control(&self) -> Result<()> {
-        info!("Initializing manual fan control");
-        self.write_register(REG_MANUAL_CONTROL, VAL_MANUAL_CONTROL_ENABLE).await?;
-        self.write_register(REG_CPU_FAN_MODE, VAL_CPU_FAN_MANUAL).await?;
-        self.write_register(REG_GPU_FAN_MODE, VAL_GPU_FAN_MANUAL).await?;
-        info!("Manual fan control enabled");
-        Ok(())
+        let cpu_target = config.cpu.speed_at(cpu_temp);
+        let gpu_target = config.gpu.speed_at(gpu_temp);
+        let cpu_speed = cpu_state.next(cpu_target, cpu_temp, config.hysteresis_c);
+        let gpu_speed = gpu_state.next(gpu_target, gpu_temp, config.hysteresis_c);
+
+        if let Err(e) = fans.set_fan_speeds(cpu_speed, gpu_speed).await {
+            warn!("Fan curve: failed to set fan speeds: {}", e);
+        }
     }
 
-    async fn restore_auto_control(&self) -> Result<()> {
-        info!("Restoring automatic fan control");
-        self.write_register(REG_CPU_FAN_MODE, VAL_CPU_FAN_AUTO).await?;
-        self.write_register(REG_GPU_FAN_MODE, VAL_GPU_FAN_AUTO).await?;
-        self.write_register(REG_MANUAL_CONTROL, VAL_MANUAL_CONTROL_DISABLE).await?;
-        info!("Automatic fan control restored");
-        Ok(())
+    info!("Fan curve loop cancelled, restoring automatic fan control");
+    if let Err(e) = fans.restore_auto_control().await {
+        error!("Failed to restore auto control after curve cancellation: {}", e);
     }
+}
 
-    async fn set_fan_speeds(&self, cpu_speed: u8, gpu_speed: u8) -> Result<()> {
-        // Validate inputs
-        let cpu = validate_fan_speed(cpu_speed)?;
-        let gpu = validate_fan_speed(gpu_speed)?;
+/// Cancels and joins `slot`'s curve task, if any. No-op if no curve is active.
+async fn stop_curve_task(slot: &Arc<Mutex<Option<CurveTask>>>) {
+    let task = slot.lock().await.take();
+    if let Some(task) = task {
+        task.cancel.notify_one();
+        let _ = task.handle.await;
+    }
+}
 
-        info!("Setting fan speeds: CPU={}%, GPU={}%", cpu, gpu);
-        self.write_register(REG_CPU_FAN_WRITE, cpu).await?;
-        self.write_register(REG_GPU_FAN_WRITE, gpu).await?;
-        Ok(())
+/// Waits for Ctrl-C or SIGTERM, whichever comes first. systemd's default stop
+/// signal is SIGTERM, and Rust's default handler for it terminates the
+/// process immediately, so without this `systemctl stop`/`restart` would skip
+/// `stop_curve_task`/`restore_auto_control` entirely and leave the fans
+/// pinned at the last curve-commanded speed.
+async fn wait_for_shutdown_signal() -> Result<()> {
+    let mut sigterm = signal(SignalKind::terminate())
+        .context("Failed to register SIGTERM handler")?;
+    tokio::select! {
+        result = tokio::signal::ctrl_c() => result.context("Failed to listen for Ctrl-C"),
+        _ = sigterm.recv() => Ok(()),
     }
+}
+
+/// Most recently sampled fan/temperature readings, refreshed by
+/// `run_telemetry_sampler` and served as-is by the `get_fan_speeds` getter so
+/// callers don't each trigger their own EC/hwmon reads.
+#[derive(Debug, Clone, Copy, Default)]
+struct Telemetry {
+    cpu_fan_speed: u8,
+    gpu_fan_speed: u8,
+    cpu_temp_c: i32,
+    gpu_temp_c: i32,
+}
+
+/// Reads a fresh fan-speed/temperature sample, or `None` (after logging) if
+/// the fan backend couldn't be read. Used both to warm `telemetry` before the
+/// service goes live on D-Bus and by `run_telemetry_sampler`'s recurring loop.
+async fn sample_telemetry(
+    fans: &Arc<dyn FanBackend>,
+    cpu_sensor: &Arc<dyn Sensor>,
+    gpu_sensor: &Arc<dyn Sensor>,
+) -> Option<Telemetry> {
+    let (cpu_fan_speed, gpu_fan_speed) = match fans.get_fan_speeds().await {
+        Ok(speeds) => speeds,
+        Err(e) => {
+            warn!("Telemetry sampler: failed to read fan speeds: {}", e);
+            return None;
+        }
+    };
+    let cpu_temp_c = cpu_sensor.temperature_c().unwrap_or_default();
+    let gpu_temp_c = gpu_sensor.temperature_c().unwrap_or_default();
+    Some(Telemetry { cpu_fan_speed, gpu_fan_speed, cpu_temp_c, gpu_temp_c })
+}
 
-    async fn get_fan_speeds(&self) -> Result<(u8, u8)> {
-        let cpu = self.read_register(REG_CPU_FAN_READ).await?;
-        let gpu = self.read_register(REG_GPU_FAN_READ).await?;
-        Ok((cpu, gpu))
+/// Polls `fans`/`cpu_sensor`/`gpu_sensor` on a fixed interval, refreshes
+/// `telemetry`, and emits a `TelemetryUpdated` signal so clients can react to
+/// changes instead of polling `get_fan_speeds` themselves. Assumes `telemetry`
+/// has already been warmed with an initial sample by the caller.
+async fn run_telemetry_sampler(
+    fans: Arc<dyn FanBackend>,
+    cpu_sensor: Arc<dyn Sensor>,
+    gpu_sensor: Arc<dyn Sensor>,
+    telemetry: Arc<Mutex<Telemetry>>,
+    emitter: SignalEmitter<'static>,
+    interval: Duration,
+) {
+    loop {
+        tokio::time::sleep(interval).await;
+
+        let Some(sample) = sample_telemetry(&fans, &cpu_sensor, &gpu_sensor).await else {
+            continue;
+        };
+        *telemetry.lock().await = sample;
+
+        if let Err(e) = BoreasService::telemetry_updated(
+            &emitter,
+            sample.cpu_fan_speed,
+            sample.gpu_fan_speed,
+            sample.cpu_temp_c,
+            sample.gpu_temp_c,
+        )
+        .await
+        {
+            warn!("Failed to emit TelemetryUpdated signal: {}", e);
+        }
     }
 }
 
 struct BoreasService {
-    ec: Arc<EcController>,
+    fans: Arc<dyn FanBackend>,
+    cpu_sensor: Arc<dyn Sensor>,
+    gpu_sensor: Arc<dyn Sensor>,
+    config: Arc<Config>,
     current_profile: Arc<Mutex<FanProfile>>,
+    curve_task: Arc<Mutex<Option<CurveTask>>>,
+    telemetry: Arc<Mutex<Telemetry>>,
+    /// Raw points of the currently-active curve, if any, kept around purely
+    /// so `save_variant` can persist it without re-deriving it from the
+    /// running `CurveTask`.
+    current_curve: Arc<Mutex<Option<FanVariant>>>,
+    variants: Arc<Mutex<VariantStore>>,
+    current_variant: Arc<Mutex<Option<String>>>,
 }
 
 #[interface(name = "org.jesternet.Boreas")]
@@ -206,21 +375,23 @@ impl BoreasService {
 
         info!("Setting fan profile to: {:?}", profile_enum);
 
+        stop_curve_task(&self.curve_task).await;
+        *self.current_curve.lock().await = None;
+        *self.current_variant.lock().await = None;
+
         if matches!(profile_enum, FanProfile::Auto) {
-            if let Err(e) = self.ec.restore_auto_control().await {
+            if let Err(e) = self.fans.restore_auto_control().await {
                 error!("Failed to restore auto control: {}", e);
                 return Err(zbus::fdo::Error::Failed(format!("EC error: {}", e)));
             }
         } else {
-            if let Err(e) = self.ec.initialize_manual_control().await {
+            if let Err(e) = self.fans.initialize_manual_control().await {
                 error!("Failed to initialize manual control: {}", e);
                 return Err(zbus::fdo::Error::Failed(format!("EC error: {}", e)));
             }
 
-            if let Err(e) = self.ec.set_fan_speeds(
-                profile_enum.cpu_speed(),
-                profile_enum.gpu_speed()
-            ).await {
+            let (cpu_speed, gpu_speed) = profile_enum.speeds(&self.config);
+            if let Err(e) = self.fans.set_fan_speeds(cpu_speed, gpu_speed).await {
                 error!("Failed to set fan speeds: {}", e);
                 return Err(zbus::fdo::Error::Failed(format!("EC error: {}", e)));
             }
@@ -231,20 +402,144 @@ impl BoreasService {
         Ok(format!("Fan profile set to: {}", profile))
     }
 
-    async fn get_fan_speeds(&self) -> zbus::fdo::Result<(u8, u8)> {
-        match self.ec.get_fan_speeds().await {
-            Ok(speeds) => Ok(speeds),
-            Err(e) => {
-                error!("Failed to read fan speeds: {}", e);
-                Err(zbus::fdo::Error::Failed(format!("EC error: {}", e)))
-            }
+    /// Switches to a closed-loop curve profile: `cpu_points`/`gpu_points` are
+    /// each a list of (°C, speed%) pairs, and `hysteresis_c` is the minimum
+    /// temperature drop required before a fan's speed is allowed to decrease.
+    /// Pass a negative `hysteresis_c` to use `[curve].hysteresis_c` from config.
+    async fn set_fan_curve(
+        &self,
+        cpu_points: Vec<(i32, u8)>,
+        gpu_points: Vec<(i32, u8)>,
+        hysteresis_c: i32,
+    ) -> zbus::fdo::Result<String> {
+        let hysteresis_c = if hysteresis_c < 0 {
+            self.config.curve.hysteresis_c
+        } else {
+            hysteresis_c
+        };
+
+        let variant = FanVariant::Curve {
+            cpu_points: cpu_points.clone(),
+            gpu_points: gpu_points.clone(),
+            hysteresis_c,
+        };
+
+        let cpu_curve = FanCurve::new(cpu_points)
+            .map_err(|e| zbus::fdo::Error::InvalidArgs(format!("Invalid CPU fan curve: {}", e)))?;
+        let gpu_curve = FanCurve::new(gpu_points)
+            .map_err(|e| zbus::fdo::Error::InvalidArgs(format!("Invalid GPU fan curve: {}", e)))?;
+
+        info!("Setting fan profile to: Curve");
+
+        stop_curve_task(&self.curve_task).await;
+        *self.current_variant.lock().await = None;
+
+        if let Err(e) = self.fans.initialize_manual_control().await {
+            error!("Failed to initialize manual control: {}", e);
+            return Err(zbus::fdo::Error::Failed(format!("EC error: {}", e)));
         }
+
+        let config = CurveConfig {
+            cpu: cpu_curve,
+            gpu: gpu_curve,
+            hysteresis_c,
+            sample_interval: self.config.curve.sample_interval(),
+        };
+        let cancel = Arc::new(Notify::new());
+        let handle = tokio::spawn(run_curve_loop(
+            self.fans.clone(),
+            self.cpu_sensor.clone(),
+            self.gpu_sensor.clone(),
+            config,
+            cancel.clone(),
+        ));
+        *self.curve_task.lock().await = Some(CurveTask { handle, cancel });
+        *self.current_profile.lock().await = FanProfile::Curve;
+        *self.current_curve.lock().await = Some(variant);
+
+        Ok("Fan profile set to: curve".to_string())
+    }
+
+    /// Returns the last sampled fan speeds rather than reading the EC again;
+    /// see `run_telemetry_sampler` for how the cache is kept fresh.
+    async fn get_fan_speeds(&self) -> zbus::fdo::Result<(u8, u8)> {
+        let t = self.telemetry.lock().await;
+        Ok((t.cpu_fan_speed, t.gpu_fan_speed))
     }
 
     async fn get_current_profile(&self) -> String {
         let profile = *self.current_profile.lock().await;
-        format!("{:?}", profile)
+        match &*self.current_variant.lock().await {
+            Some(name) => format!("{:?} (variant: {})", profile, name),
+            None => format!("{:?}", profile),
+        }
     }
+
+    /// Persists the currently-active profile or curve under `name`, so it can
+    /// later be restored with `load_variant`. Saving over the name
+    /// `"default"` makes it the variant auto-applied on the next startup.
+    async fn save_variant(&self, name: &str) -> zbus::fdo::Result<String> {
+        let profile = *self.current_profile.lock().await;
+        let variant = if matches!(profile, FanProfile::Curve) {
+            self.current_curve
+                .lock()
+                .await
+                .clone()
+                .ok_or_else(|| zbus::fdo::Error::Failed("No active curve to save".to_string()))?
+        } else {
+            FanVariant::Profile { profile: profile.variant_name().to_string() }
+        };
+
+        let mut store = self.variants.lock().await;
+        store.variants.insert(name.to_string(), variant);
+        store
+            .save(DEFAULT_VARIANTS_PATH)
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to save variant: {}", e)))?;
+
+        Ok(format!("Saved variant '{}'", name))
+    }
+
+    /// Re-applies the profile or curve previously saved as `name`.
+    async fn load_variant(&self, name: &str) -> zbus::fdo::Result<String> {
+        let variant = {
+            let store = self.variants.lock().await;
+            store
+                .variants
+                .get(name)
+                .cloned()
+                .ok_or_else(|| zbus::fdo::Error::Failed(format!("No such variant: {}", name)))?
+        };
+
+        match &variant {
+            FanVariant::Profile { profile } => {
+                self.set_fan_profile(profile).await?;
+            }
+            FanVariant::Curve { cpu_points, gpu_points, hysteresis_c } => {
+                self.set_fan_curve(cpu_points.clone(), gpu_points.clone(), *hysteresis_c)
+                    .await?;
+            }
+        }
+
+        *self.current_variant.lock().await = Some(name.to_string());
+        Ok(format!("Loaded variant '{}'", name))
+    }
+
+    /// Lists the names of all persisted variants.
+    async fn list_variants(&self) -> Vec<String> {
+        self.variants.lock().await.variants.keys().cloned().collect()
+    }
+
+    /// Emitted on each telemetry sample; carries the same readings as
+    /// `get_fan_speeds` plus CPU/GPU temperature, so clients can subscribe
+    /// instead of polling.
+    #[zbus(signal)]
+    async fn telemetry_updated(
+        emitter: &SignalEmitter<'_>,
+        cpu_fan_speed: u8,
+        gpu_fan_speed: u8,
+        cpu_temp_c: i32,
+        gpu_temp_c: i32,
+    ) -> zbus::Result<()>;
 }
 
 #[tokio::main]
@@ -255,28 +550,86 @@ async fn main() -> Result<()> {
     info!("Version: 1.0.0");
     info!("Project: https://github.com/yourrepo/boreas");
 
-    // CRITICAL: Verify hardware compatibility before proceeding
-    verify_hardware()?;
+    let config = Arc::new(Config::load(config::DEFAULT_CONFIG_PATH)?);
+    let dev_mode = dev_mode_requested();
 
-    let ec = Arc::new(EcController::new()?);
+    let fans: Arc<dyn FanBackend> = if dev_mode {
+        warn!("Running in --dev mode: hardware safety check skipped, EC writes are logged only");
+        Arc::new(DevFanBackend::new())
+    } else {
+        // CRITICAL: Verify hardware compatibility before proceeding
+        let registers = verify_hardware(&config)?;
+        Arc::new(EcFanBackend::new(registers)?)
+    };
+
+    // Real hwmon/thermal_zone sensors are safe to read even off real Nitro
+    // hardware, but CI boxes may not expose a matching driver at all, so
+    // `--dev` uses fixed readings rather than failing every sample.
+    let (cpu_sensor, gpu_sensor): (Arc<dyn Sensor>, Arc<dyn Sensor>) = if dev_mode {
+        (Arc::new(ConstantSensor(45)), Arc::new(ConstantSensor(50)))
+    } else {
+        (Arc::new(HwmonSensor::cpu()), Arc::new(HwmonSensor::gpu()))
+    };
+    let curve_task: Arc<Mutex<Option<CurveTask>>> = Arc::new(Mutex::new(None));
+    let telemetry = Arc::new(Mutex::new(Telemetry::default()));
+    // Warm the cache with a live read before the service goes live on D-Bus,
+    // so `get_fan_speeds` never hands out a zeroed placeholder to an early caller.
+    if let Some(sample) = sample_telemetry(&fans, &cpu_sensor, &gpu_sensor).await {
+        *telemetry.lock().await = sample;
+    }
+    let variant_store = Arc::new(Mutex::new(VariantStore::load(DEFAULT_VARIANTS_PATH)?));
+    let has_default_variant = variant_store.lock().await.variants.contains_key(DEFAULT_VARIANT_NAME);
 
     let service = BoreasService {
-        ec: ec.clone(),
+        fans: fans.clone(),
+        cpu_sensor: cpu_sensor.clone(),
+        gpu_sensor: gpu_sensor.clone(),
+        config: config.clone(),
         current_profile: Arc::new(Mutex::new(FanProfile::Auto)),
+        curve_task: curve_task.clone(),
+        telemetry: telemetry.clone(),
+        current_curve: Arc::new(Mutex::new(None)),
+        variants: variant_store,
+        current_variant: Arc::new(Mutex::new(None)),
     };
 
     info!("Connecting to system D-Bus...");
-    let _conn = ConnectionBuilder::system()?
+    let conn = ConnectionBuilder::system()?
         .name("org.jesternet.Boreas")?
         .serve_at("/org/jesternet/Boreas", service)?
         .build()
         .await?;
 
     info!("Boreas daemon ready on D-Bus: org.jesternet.Boreas");
-    info!("Available profiles: silent, balanced, maxpower, auto");
+    info!("Available profiles: silent, balanced, maxpower, auto, curve");
+
+    let iface_ref = conn
+        .object_server()
+        .interface::<_, BoreasService>("/org/jesternet/Boreas")
+        .await?;
+    tokio::spawn(run_telemetry_sampler(
+        fans,
+        cpu_sensor,
+        gpu_sensor,
+        telemetry,
+        iface_ref.signal_emitter().to_owned(),
+        config.telemetry.interval(),
+    ));
+
+    if has_default_variant {
+        info!("Restoring '{}' fan variant from previous session", DEFAULT_VARIANT_NAME);
+        let iface = iface_ref.get().await;
+        if let Err(e) = iface.load_variant(DEFAULT_VARIANT_NAME).await {
+            warn!("Failed to restore default fan variant: {}", e);
+        }
+    }
 
-    // Keep running
-    std::future::pending::<()>().await;
+    // Keep running until shutdown (Ctrl-C or systemd's SIGTERM), then make
+    // sure a running curve loop hands control back to hardware auto mode
+    // rather than leaving fans pinned.
+    wait_for_shutdown_signal().await?;
+    info!("Shutdown signal received, cleaning up");
+    stop_curve_task(&curve_task).await;
 
     Ok(())
 }
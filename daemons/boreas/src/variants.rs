@@ -0,0 +1,65 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use tracing::info;
+
+/// Default location for persisted fan variants.
+pub const DEFAULT_VARIANTS_PATH: &str = "/var/lib/boreas/variants.toml";
+
+/// Name of the variant auto-applied at startup, if one has been saved.
+pub const DEFAULT_VARIANT_NAME: &str = "default";
+
+/// A named, persisted fan configuration: either a reference to one of the
+/// static `[profiles.<name>]` entries, automatic control, or an explicit
+/// curve (since curves have no config-file counterpart to reference by name).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum FanVariant {
+    Profile { profile: String },
+    Curve {
+        cpu_points: Vec<(i32, u8)>,
+        gpu_points: Vec<(i32, u8)>,
+        hysteresis_c: i32,
+    },
+}
+
+/// On-disk store of named fan variants, keyed by variant name.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VariantStore {
+    pub variants: HashMap<String, FanVariant>,
+}
+
+impl VariantStore {
+    /// Loads variants from `path`, falling back to an empty store when the
+    /// file does not exist. A present-but-malformed file is a startup error.
+    pub fn load(path: &str) -> Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(contents) => {
+                info!("Loading fan variants from {}", path);
+                toml::from_str(&contents)
+                    .with_context(|| format!("Failed to parse variants file {}", path))
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                info!("No variants file at {}, starting with none saved", path);
+                Ok(Self::default())
+            }
+            Err(e) => Err(e).with_context(|| format!("Failed to read variants file {}", path)),
+        }
+    }
+
+    /// Serializes and writes the store to `path`, creating its parent
+    /// directory if necessary.
+    pub fn save(&self, path: &str) -> Result<()> {
+        if let Some(parent) = Path::new(path).parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory for {}", path))?;
+        }
+        let contents = toml::to_string_pretty(self).context("Failed to serialize fan variants")?;
+        fs::write(path, contents).with_context(|| format!("Failed to write variants file {}", path))?;
+        info!("Saved fan variants to {}", path);
+        Ok(())
+    }
+}
@@ -0,0 +1,304 @@
+use anyhow::{Context, Result};
+use glob::glob;
+use std::fs;
+use std::path::Path;
+use tracing::{info, warn};
+
+use crate::config::ProfileSettings;
+
+const CPU_GOVERNOR_PATH: &str = "/sys/devices/system/cpu/cpu*/cpufreq/scaling_governor";
+const CPU_EPP_PATH: &str = "/sys/devices/system/cpu/cpu*/cpufreq/energy_performance_preference";
+const TURBO_PATH: &str = "/sys/devices/system/cpu/intel_pstate/no_turbo";
+const RAPL_BASE: &str = "/sys/class/powercap/intel-rapl:0";
+
+/// Default PL1 averaging window: long enough that PL1 tracks a sustained
+/// thermal budget rather than reacting to momentary spikes.
+const DEFAULT_PL1_TIME_WINDOW_US: u64 = 28_000_000;
+/// Default PL2 averaging window: short enough that PL2 only bounds brief
+/// bursts above PL1, rather than acting like a second sustained limit.
+const DEFAULT_PL2_TIME_WINDOW_US: u64 = 2_440;
+
+/// Abstraction over CPU frequency-scaling control, so the D-Bus service
+/// depends on a trait object rather than sysfs paths directly. This is what
+/// lets `DevCpuBackend` stand in for `RealCpuBackend` under `--dev`.
+pub trait CpuBackend: Send + Sync {
+    fn apply_profile(&self, profile_name: &str, settings: &ProfileSettings) -> Result<()>;
+    fn get_current_governor(&self) -> Result<String>;
+    fn get_current_epp(&self) -> Result<String>;
+    fn get_turbo_enabled(&self) -> Result<bool>;
+
+    /// Sets PL1 (sustained) and PL2 (burst) power limits in watts, clamped to
+    /// the hardware-reported max. Returns the limits actually applied.
+    fn set_power_limits(&self, pl1_watts: f64, pl2_watts: f64) -> Result<(f64, f64)>;
+    fn get_power_limits(&self) -> Result<(f64, f64)>;
+}
+
+/// Writes governor/EPP/turbo settings through the real `cpufreq`/`intel_pstate` sysfs interfaces.
+pub struct RealCpuBackend;
+
+impl RealCpuBackend {
+    pub fn new() -> Result<Self> {
+        // Verify we have access to CPU control interfaces
+        if !std::path::Path::new(TURBO_PATH).exists() {
+            anyhow::bail!("Intel P-State driver not available");
+        }
+        Ok(Self)
+    }
+
+    fn set_governor(&self, governor: &str) -> Result<()> {
+        info!("Setting CPU governor to: {}", governor);
+
+        let paths: Vec<_> = glob(CPU_GOVERNOR_PATH)
+            .context("Failed to glob governor paths")?
+            .filter_map(Result::ok)
+            .collect();
+
+        if paths.is_empty() {
+            anyhow::bail!("No CPU governor control files found");
+        }
+
+        let count = paths.len();
+        for path in paths {
+            fs::write(&path, governor)
+                .with_context(|| format!("Failed to write to {:?}", path))?;
+        }
+
+        info!("Governor set for {} CPUs", count);
+        Ok(())
+    }
+
+    fn set_epp(&self, epp: &str) -> Result<()> {
+        info!("Setting Energy Performance Preference to: {}", epp);
+
+        let paths: Vec<_> = glob(CPU_EPP_PATH)
+            .context("Failed to glob EPP paths")?
+            .filter_map(Result::ok)
+            .collect();
+
+        if paths.is_empty() {
+            warn!("No EPP control files found (may not be supported)");
+            return Ok(());
+        }
+
+        let count = paths.len();
+        for path in paths {
+            if let Err(e) = fs::write(&path, epp) {
+                warn!("Failed to write EPP to {:?}: {}", path, e);
+            }
+        }
+
+        info!("EPP set for {} CPUs", count);
+        Ok(())
+    }
+
+    fn set_turbo(&self, enabled: bool) -> Result<()> {
+        let value = if enabled { "0" } else { "1" }; // 0 = turbo enabled, 1 = disabled
+        info!("Setting turbo boost: {}", if enabled { "enabled" } else { "disabled" });
+
+        fs::write(TURBO_PATH, value)
+            .context("Failed to write turbo setting")?;
+
+        Ok(())
+    }
+
+    fn rapl_available(&self) -> bool {
+        Path::new(RAPL_BASE).exists()
+    }
+
+    fn read_rapl_uw(&self, file: &str) -> Result<u64> {
+        fs::read_to_string(Path::new(RAPL_BASE).join(file))
+            .with_context(|| format!("Failed to read {}/{}", RAPL_BASE, file))?
+            .trim()
+            .parse()
+            .with_context(|| format!("Failed to parse {}/{} as microwatts", RAPL_BASE, file))
+    }
+
+    fn write_rapl_uw(&self, file: &str, microwatts: u64) -> Result<()> {
+        fs::write(Path::new(RAPL_BASE).join(file), microwatts.to_string())
+            .with_context(|| format!("Failed to write {}/{}", RAPL_BASE, file))
+    }
+
+    fn max_power_watts(&self) -> Result<(f64, f64)> {
+        let pl1_max = self.read_rapl_uw("constraint_0_max_power_uw")?;
+        let pl2_max = self.read_rapl_uw("constraint_1_max_power_uw")?;
+        Ok((pl1_max as f64 / 1_000_000.0, pl2_max as f64 / 1_000_000.0))
+    }
+
+    /// Sets the PL1/PL2 averaging windows. Without this, PL1 and PL2 are just
+    /// two power limits with no time component, which doesn't actually
+    /// distinguish "sustained" from "burst" on real RAPL hardware.
+    fn set_time_windows(&self, pl1_us: u64, pl2_us: u64) -> Result<()> {
+        self.write_rapl_uw("constraint_0_time_window_us", pl1_us)?;
+        self.write_rapl_uw("constraint_1_time_window_us", pl2_us)?;
+        Ok(())
+    }
+}
+
+impl CpuBackend for RealCpuBackend {
+    fn apply_profile(&self, profile_name: &str, settings: &ProfileSettings) -> Result<()> {
+        info!("Applying performance profile: {}", profile_name);
+
+        self.set_governor(&settings.governor)?;
+        self.set_epp(&settings.epp)?;
+        self.set_turbo(settings.turbo)?;
+
+        if self.rapl_available() {
+            let (pl1_max, pl2_max) = self.max_power_watts().unwrap_or((0.0, 0.0));
+            let pl1_watts = settings.pl1_watts.unwrap_or(pl1_max);
+            let pl2_watts = settings.pl2_watts.unwrap_or(pl2_max);
+            if let Err(e) = self.set_power_limits(pl1_watts, pl2_watts) {
+                warn!("Failed to set power limits for profile {}: {}", profile_name, e);
+            }
+
+            let pl1_window_us = settings.pl1_time_window_us.unwrap_or(DEFAULT_PL1_TIME_WINDOW_US);
+            let pl2_window_us = settings.pl2_time_window_us.unwrap_or(DEFAULT_PL2_TIME_WINDOW_US);
+            if let Err(e) = self.set_time_windows(pl1_window_us, pl2_window_us) {
+                warn!("Failed to set RAPL time windows for profile {}: {}", profile_name, e);
+            }
+        } else {
+            warn!("Intel RAPL powercap interface not found at {}, skipping power limits for profile {}", RAPL_BASE, profile_name);
+        }
+
+        info!("Performance profile {} applied successfully", profile_name);
+        Ok(())
+    }
+
+    fn get_current_governor(&self) -> Result<String> {
+        let paths: Vec<_> = glob(CPU_GOVERNOR_PATH)
+            .context("Failed to glob governor paths")?
+            .filter_map(Result::ok)
+            .collect();
+
+        if let Some(path) = paths.first() {
+            let governor = fs::read_to_string(path)
+                .context("Failed to read governor")?
+                .trim()
+                .to_string();
+            Ok(governor)
+        } else {
+            anyhow::bail!("No governor paths found")
+        }
+    }
+
+    fn get_current_epp(&self) -> Result<String> {
+        let paths: Vec<_> = glob(CPU_EPP_PATH)
+            .context("Failed to glob EPP paths")?
+            .filter_map(Result::ok)
+            .collect();
+
+        if let Some(path) = paths.first() {
+            let epp = fs::read_to_string(path)
+                .context("Failed to read EPP")?
+                .trim()
+                .to_string();
+            Ok(epp)
+        } else {
+            Ok("not_supported".to_string())
+        }
+    }
+
+    fn get_turbo_enabled(&self) -> Result<bool> {
+        let contents = fs::read_to_string(TURBO_PATH).context("Failed to read turbo state")?;
+        // 0 = turbo enabled, 1 = disabled; see `set_turbo`.
+        Ok(contents.trim() == "0")
+    }
+
+    fn set_power_limits(&self, pl1_watts: f64, pl2_watts: f64) -> Result<(f64, f64)> {
+        if !self.rapl_available() {
+            anyhow::bail!("Intel RAPL powercap interface not found at {}", RAPL_BASE);
+        }
+
+        let (pl1_max, pl2_max) = self.max_power_watts()?;
+        let pl1_applied = pl1_watts.clamp(0.0, pl1_max);
+        let pl2_applied = pl2_watts.clamp(0.0, pl2_max);
+
+        self.write_rapl_uw("constraint_0_power_limit_uw", (pl1_applied * 1_000_000.0) as u64)?;
+        self.write_rapl_uw("constraint_1_power_limit_uw", (pl2_applied * 1_000_000.0) as u64)?;
+        // Always (re)write matching time windows: without these, PL1/PL2 are
+        // just two power caps with no averaging period, which doesn't
+        // actually implement sustained-vs-burst behavior.
+        self.set_time_windows(DEFAULT_PL1_TIME_WINDOW_US, DEFAULT_PL2_TIME_WINDOW_US)?;
+
+        info!(
+            "Set power limits: PL1={:.1}W ({}us window), PL2={:.1}W ({}us window)",
+            pl1_applied, DEFAULT_PL1_TIME_WINDOW_US, pl2_applied, DEFAULT_PL2_TIME_WINDOW_US
+        );
+        Ok((pl1_applied, pl2_applied))
+    }
+
+    fn get_power_limits(&self) -> Result<(f64, f64)> {
+        if !self.rapl_available() {
+            anyhow::bail!("Intel RAPL powercap interface not found at {}", RAPL_BASE);
+        }
+
+        let pl1 = self.read_rapl_uw("constraint_0_power_limit_uw")?;
+        let pl2 = self.read_rapl_uw("constraint_1_power_limit_uw")?;
+        Ok((pl1 as f64 / 1_000_000.0, pl2 as f64 / 1_000_000.0))
+    }
+}
+
+/// Logs what it would write instead of touching cpufreq/intel_pstate sysfs.
+/// Selected via `--dev` or `PROMETHEUS_DEV=1`; also bypasses
+/// `verify_hardware()`, so this is what lets contributors develop and run
+/// integration tests on non-Nitro machines and in CI.
+pub struct DevCpuBackend {
+    last_governor: std::sync::Mutex<String>,
+    last_epp: std::sync::Mutex<String>,
+    last_turbo: std::sync::Mutex<bool>,
+    last_power_limits: std::sync::Mutex<(f64, f64)>,
+}
+
+impl DevCpuBackend {
+    pub fn new() -> Self {
+        Self {
+            last_governor: std::sync::Mutex::new("unknown".to_string()),
+            last_epp: std::sync::Mutex::new("unknown".to_string()),
+            last_turbo: std::sync::Mutex::new(false),
+            last_power_limits: std::sync::Mutex::new((0.0, 0.0)),
+        }
+    }
+}
+
+impl Default for DevCpuBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CpuBackend for DevCpuBackend {
+    fn apply_profile(&self, profile_name: &str, settings: &ProfileSettings) -> Result<()> {
+        info!(
+            "[dev] would apply profile {}: governor={}, epp={}, turbo={}, pl1={:?}W ({:?}us), pl2={:?}W ({:?}us)",
+            profile_name, settings.governor, settings.epp, settings.turbo,
+            settings.pl1_watts, settings.pl1_time_window_us,
+            settings.pl2_watts, settings.pl2_time_window_us,
+        );
+        *self.last_governor.lock().unwrap() = settings.governor.clone();
+        *self.last_epp.lock().unwrap() = settings.epp.clone();
+        *self.last_turbo.lock().unwrap() = settings.turbo;
+        *self.last_power_limits.lock().unwrap() = (settings.pl1_watts.unwrap_or(0.0), settings.pl2_watts.unwrap_or(0.0));
+        Ok(())
+    }
+
+    fn get_current_governor(&self) -> Result<String> {
+        Ok(self.last_governor.lock().unwrap().clone())
+    }
+
+    fn get_current_epp(&self) -> Result<String> {
+        Ok(self.last_epp.lock().unwrap().clone())
+    }
+
+    fn get_turbo_enabled(&self) -> Result<bool> {
+        Ok(*self.last_turbo.lock().unwrap())
+    }
+
+    fn set_power_limits(&self, pl1_watts: f64, pl2_watts: f64) -> Result<(f64, f64)> {
+        info!("[dev] would set power limits: PL1={:.1}W, PL2={:.1}W", pl1_watts, pl2_watts);
+        *self.last_power_limits.lock().unwrap() = (pl1_watts, pl2_watts);
+        Ok((pl1_watts, pl2_watts))
+    }
+
+    fn get_power_limits(&self) -> Result<(f64, f64)> {
+        Ok(*self.last_power_limits.lock().unwrap())
+    }
+}
@@ -0,0 +1,160 @@
+use anyhow::Context;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::time::Duration;
+use tracing::info;
+
+/// Default location for the Prometheus configuration file.
+pub const DEFAULT_CONFIG_PATH: &str = "/etc/prometheus/config.toml";
+
+/// A named performance profile's governor/EPP/turbo/power-limit settings,
+/// settable via `[profiles.<name>]`. `pl1_watts`/`pl2_watts` of `None` means
+/// "use the hardware-reported package max" (clamped by RAPL regardless).
+/// `pl1_time_window_us`/`pl2_time_window_us` of `None` falls back to the
+/// backend's built-in sustained/burst averaging windows — this is what
+/// actually makes PL1 "sustained" and PL2 "burst" rather than just two power
+/// caps with no time component.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProfileSettings {
+    pub governor: String,
+    pub epp: String,
+    pub turbo: bool,
+    #[serde(default)]
+    pub pl1_watts: Option<f64>,
+    #[serde(default)]
+    pub pl2_watts: Option<f64>,
+    #[serde(default)]
+    pub pl1_time_window_us: Option<u64>,
+    #[serde(default)]
+    pub pl2_time_window_us: Option<u64>,
+}
+
+/// Background telemetry sampling parameters, overridable via `[telemetry]`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct TelemetrySettings {
+    pub interval_secs: u64,
+}
+
+impl Default for TelemetrySettings {
+    fn default() -> Self {
+        Self { interval_secs: 2 }
+    }
+}
+
+impl TelemetrySettings {
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval_secs)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub profiles: HashMap<String, ProfileSettings>,
+    /// Hardware safety lock allowlist. New boards can be supported by adding
+    /// an entry here rather than recompiling the daemon.
+    pub supported_models: Vec<String>,
+    pub telemetry: TelemetrySettings,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        let mut profiles = HashMap::new();
+        profiles.insert(
+            "silent".to_string(),
+            ProfileSettings {
+                governor: "powersave".to_string(),
+                epp: "power".to_string(),
+                turbo: false,
+                pl1_watts: Some(35.0),
+                pl2_watts: Some(45.0),
+                pl1_time_window_us: None,
+                pl2_time_window_us: None,
+            },
+        );
+        profiles.insert(
+            "balanced".to_string(),
+            ProfileSettings {
+                governor: "powersave".to_string(),
+                epp: "balance_performance".to_string(),
+                turbo: true,
+                pl1_watts: Some(55.0),
+                pl2_watts: Some(90.0),
+                pl1_time_window_us: None,
+                pl2_time_window_us: None,
+            },
+        );
+        profiles.insert(
+            "warspeed".to_string(),
+            ProfileSettings {
+                governor: "performance".to_string(),
+                epp: "performance".to_string(),
+                turbo: true,
+                pl1_watts: None, // package max
+                pl2_watts: None, // package max
+                pl1_time_window_us: None,
+                pl2_time_window_us: None,
+            },
+        );
+
+        Self {
+            profiles,
+            supported_models: vec!["Nitro AN515-57".to_string()],
+            telemetry: TelemetrySettings::default(),
+        }
+    }
+}
+
+impl Config {
+    /// Loads configuration from `path`, falling back to built-in defaults
+    /// when the file does not exist. A present-but-malformed file is a
+    /// startup error.
+    pub fn load(path: &str) -> anyhow::Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => {
+                info!("Loading configuration from {}", path);
+                let mut config: Config = toml::from_str(&contents)
+                    .with_context(|| format!("Failed to parse config file {}", path))?;
+                config.merge_default_supported_models();
+                config.merge_default_profiles();
+                Ok(config)
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                info!("No config file at {}, using built-in defaults", path);
+                Ok(Config::default())
+            }
+            Err(e) => Err(e).with_context(|| format!("Failed to read config file {}", path)),
+        }
+    }
+
+    /// Adds the built-in supported models to `self.supported_models` if
+    /// they're not already present. Without this, a config file that sets
+    /// `supported_models` at all would replace the whole list (since
+    /// `#[serde(default)]` only fills in *missing* top-level fields),
+    /// silently dropping built-in support for the Nitro AN515-57.
+    fn merge_default_supported_models(&mut self) {
+        for model in Config::default().supported_models {
+            if !self.supported_models.contains(&model) {
+                self.supported_models.push(model);
+            }
+        }
+    }
+
+    /// Adds the built-in `silent`/`balanced`/`warspeed` profiles for any name
+    /// not already defined in `self.profiles`. Without this, a config file
+    /// that defines even one `[profiles.<name>]` table would replace the
+    /// whole `profiles` map (since `#[serde(default)]` only fills in
+    /// *missing* top-level fields), silently dropping the built-in profiles
+    /// and turning `set_performance_profile("silent")` into a hard D-Bus
+    /// error.
+    fn merge_default_profiles(&mut self) {
+        for (name, settings) in Config::default().profiles {
+            self.profiles.entry(name).or_insert(settings);
+        }
+    }
+
+    pub fn is_supported(&self, product_name: &str) -> bool {
+        self.supported_models.iter().any(|model| product_name.contains(model.as_str()))
+    }
+}
@@ -1,23 +1,30 @@
 use anyhow::{Context, Result};
-use glob::glob;
 use std::fs;
 use std::sync::Arc;
 use tokio::sync::Mutex;
-use tracing::{info, warn, error};
+use tracing::{info, error, warn};
+use zbus::object_server::SignalEmitter;
 use zbus::{interface, ConnectionBuilder};
 
-const CPU_GOVERNOR_PATH: &str = "/sys/devices/system/cpu/cpu*/cpufreq/scaling_governor";
-const CPU_EPP_PATH: &str = "/sys/devices/system/cpu/cpu*/cpufreq/energy_performance_preference";
-const TURBO_PATH: &str = "/sys/devices/system/cpu/intel_pstate/no_turbo";
+mod backend;
+mod config;
+mod variants;
 
-// Hardware Safety Lock - Supported models
-const SUPPORTED_MODELS: &[&str] = &[
-    "Nitro AN515-57",
-];
+use backend::{CpuBackend, DevCpuBackend, RealCpuBackend};
+use config::{Config, ProfileSettings};
+use variants::{PerformanceVariant, VariantStore, DEFAULT_VARIANTS_PATH, DEFAULT_VARIANT_NAME};
 
 // DMI paths for hardware identification
 const DMI_PRODUCT_NAME: &str = "/sys/class/dmi/id/product_name";
 
+/// Dry-run mode is selected with `--dev` or `PROMETHEUS_DEV=1`. It swaps in
+/// `DevCpuBackend` (which only logs what it would write) and skips
+/// `verify_hardware()`, so contributors can develop and test Prometheus on
+/// non-Nitro machines and in CI.
+fn dev_mode_requested() -> bool {
+    std::env::args().any(|a| a == "--dev") || std::env::var("PROMETHEUS_DEV").is_ok()
+}
+
 #[derive(Debug, Clone, Copy)]
 enum PerformanceProfile {
     Silent,
@@ -26,33 +33,18 @@ enum PerformanceProfile {
 }
 
 impl PerformanceProfile {
-    fn governor(&self) -> &str {
-        match self {
-            PerformanceProfile::Silent => "powersave",
-            PerformanceProfile::Balanced => "powersave",
-            PerformanceProfile::WarSpeed => "performance",
-        }
-    }
-
-    fn epp(&self) -> &str {
-        match self {
-            PerformanceProfile::Silent => "power",
-            PerformanceProfile::Balanced => "balance_performance",
-            PerformanceProfile::WarSpeed => "performance",
-        }
-    }
-
-    fn turbo_enabled(&self) -> bool {
+    /// Config key used to look up this profile's settings in `[profiles.<name>]`.
+    fn config_key(&self) -> &'static str {
         match self {
-            PerformanceProfile::Silent => false,
-            PerformanceProfile::Balanced => true,
-            PerformanceProfile::WarSpeed => true,
+            PerformanceProfile::Silent => "silent",
+            PerformanceProfile::Balanced => "balanced",
+            PerformanceProfile::WarSpeed => "warspeed",
         }
     }
 }
 
 /// Verify hardware compatibility before allowing operation
-fn verify_hardware() -> Result<()> {
+fn verify_hardware(config: &Config) -> Result<()> {
     info!("Performing hardware compatibility check...");
 
     // Read product name
@@ -63,13 +55,13 @@ fn verify_hardware() -> Result<()> {
 
     info!("Detected hardware: {}", product_name);
 
-    // Check if this hardware is supported
-    let is_supported = SUPPORTED_MODELS.iter().any(|model| product_name.contains(model));
-
-    if !is_supported {
+    // Check if this hardware is supported. The allowlist itself comes from
+    // `config.supported_models`, so new boards are supported by editing
+    // config rather than recompiling.
+    if !config.is_supported(&product_name) {
         error!("HARDWARE SAFETY LOCK ENGAGED");
         error!("Detected model: {}", product_name);
-        error!("This daemon is designed ONLY for: {:?}", SUPPORTED_MODELS);
+        error!("This daemon is designed ONLY for: {:?}", config.supported_models);
         error!("");
         error!("Running this daemon on unsupported hardware may cause:");
         error!("  - CPU instability");
@@ -79,13 +71,14 @@ fn verify_hardware() -> Result<()> {
         error!("");
         error!("If you believe your hardware should be supported, please:");
         error!("  1. Verify your exact model number");
-        error!("  2. Open an issue at: https://github.com/yourrepo/boreas");
+        error!("  2. Add it to `supported_models` in the config file");
         error!("  3. DO NOT bypass this safety check");
+        error!("  (or run with --dev / PROMETHEUS_DEV=1 to develop against a dry-run backend)");
 
         anyhow::bail!(
             "Hardware safety check failed. Detected: '{}'. Supported: {:?}",
             product_name,
-            SUPPORTED_MODELS
+            config.supported_models
         );
     }
 
@@ -93,122 +86,65 @@ fn verify_hardware() -> Result<()> {
     Ok(())
 }
 
-struct CpuController;
-
-impl CpuController {
-    fn new() -> Result<Self> {
-        // Verify we have access to CPU control interfaces
-        if !std::path::Path::new(TURBO_PATH).exists() {
-            anyhow::bail!("Intel P-State driver not available");
-        }
-        Ok(Self)
-    }
-
-    fn set_governor(&self, governor: &str) -> Result<()> {
-        info!("Setting CPU governor to: {}", governor);
-
-        let paths: Vec<_> = glob(CPU_GOVERNOR_PATH)
-            .context("Failed to glob governor paths")?
-            .filter_map(Result::ok)
-            .collect();
-
-        if paths.is_empty() {
-            anyhow::bail!("No CPU governor control files found");
-        }
-
-        let count = paths.len();
-        for path in paths {
-            fs::write(&path, governor)
-                .with_context(|| format!("Failed to write to {:?}", path))?;
-        }
-
-        info!("Governor set for {} CPUs", count);
-        Ok(())
-    }
-
-    fn set_epp(&self, epp: &str) -> Result<()> {
-        info!("Setting Energy Performance Preference to: {}", epp);
-
-        let paths: Vec<_> = glob(CPU_EPP_PATH)
-            .context("Failed to glob EPP paths")?
-            .filter_map(Result::ok)
-            .collect();
+/// Most recently sampled governor/EPP, refreshed by `run_telemetry_sampler`
+/// and served as-is by `get_cpu_status` so callers don't each trigger their
+/// own sysfs reads.
+#[derive(Debug, Clone, Default)]
+struct Telemetry {
+    governor: String,
+    epp: String,
+}
 
-        if paths.is_empty() {
-            warn!("No EPP control files found (may not be supported)");
-            return Ok(());
+/// Reads a fresh governor/EPP sample, or `None` (after logging) if the CPU
+/// backend couldn't be read. Used both to warm `telemetry` before the service
+/// goes live on D-Bus and by `run_telemetry_sampler`'s recurring loop.
+fn sample_telemetry(cpu: &Arc<dyn CpuBackend>) -> Option<Telemetry> {
+    match (cpu.get_current_governor(), cpu.get_current_epp()) {
+        (Ok(governor), Ok(epp)) => Some(Telemetry { governor, epp }),
+        (Err(e), _) | (_, Err(e)) => {
+            warn!("Telemetry sampler: failed to read CPU status: {}", e);
+            None
         }
-
-        let count = paths.len();
-        for path in paths {
-            if let Err(e) = fs::write(&path, epp) {
-                warn!("Failed to write EPP to {:?}: {}", path, e);
-            }
-        }
-
-        info!("EPP set for {} CPUs", count);
-        Ok(())
-    }
-
-    fn set_turbo(&self, enabled: bool) -> Result<()> {
-        let value = if enabled { "0" } else { "1" }; // 0 = turbo enabled, 1 = disabled
-        info!("Setting turbo boost: {}", if enabled { "enabled" } else { "disabled" });
-
-        fs::write(TURBO_PATH, value)
-            .context("Failed to write turbo setting")?;
-
-        Ok(())
-    }
-
-    fn apply_profile(&self, profile: PerformanceProfile) -> Result<()> {
-        info!("Applying performance profile: {:?}", profile);
-
-        self.set_governor(profile.governor())?;
-        self.set_epp(profile.epp())?;
-        self.set_turbo(profile.turbo_enabled())?;
-
-        info!("Performance profile {:?} applied successfully", profile);
-        Ok(())
     }
+}
 
-    fn get_current_governor(&self) -> Result<String> {
-        let paths: Vec<_> = glob(CPU_GOVERNOR_PATH)
-            .context("Failed to glob governor paths")?
-            .filter_map(Result::ok)
-            .collect();
-
-        if let Some(path) = paths.first() {
-            let governor = fs::read_to_string(path)
-                .context("Failed to read governor")?
-                .trim()
-                .to_string();
-            Ok(governor)
-        } else {
-            anyhow::bail!("No governor paths found")
-        }
-    }
+/// Polls `cpu` on a fixed interval, refreshes `telemetry`, and emits a
+/// `TelemetryUpdated` signal so clients can react to changes instead of
+/// polling `get_cpu_status` themselves. Assumes `telemetry` has already been
+/// warmed with an initial sample by the caller.
+async fn run_telemetry_sampler(
+    cpu: Arc<dyn CpuBackend>,
+    telemetry: Arc<Mutex<Telemetry>>,
+    emitter: SignalEmitter<'static>,
+    interval: std::time::Duration,
+) {
+    loop {
+        tokio::time::sleep(interval).await;
+
+        let Some(sample) = sample_telemetry(&cpu) else {
+            continue;
+        };
 
-    fn get_current_epp(&self) -> Result<String> {
-        let paths: Vec<_> = glob(CPU_EPP_PATH)
-            .context("Failed to glob EPP paths")?
-            .filter_map(Result::ok)
-            .collect();
-
-        if let Some(path) = paths.first() {
-            let epp = fs::read_to_string(path)
-                .context("Failed to read EPP")?
-                .trim()
-                .to_string();
-            Ok(epp)
-        } else {
-            Ok("not_supported".to_string())
+        if let Err(e) = PrometheusService::telemetry_updated(
+            &emitter,
+            sample.governor.clone(),
+            sample.epp.clone(),
+        )
+        .await
+        {
+            warn!("Failed to emit TelemetryUpdated signal: {}", e);
         }
+        *telemetry.lock().await = sample;
     }
 }
 
 struct PrometheusService {
-    cpu: Arc<CpuController>,
+    cpu: Arc<dyn CpuBackend>,
+    config: Arc<Config>,
     current_profile: Arc<Mutex<Option<PerformanceProfile>>>,
+    telemetry: Arc<Mutex<Telemetry>>,
+    variants: Arc<Mutex<VariantStore>>,
+    current_variant: Arc<Mutex<Option<String>>>,
 }
 
 #[interface(name = "org.jesternet.Prometheus")]
@@ -227,33 +163,136 @@ impl PrometheusService {
 
         info!("Setting performance profile to: {:?}", profile_enum);
 
-        if let Err(e) = self.cpu.apply_profile(profile_enum) {
+        let settings = self.config.profiles.get(profile_enum.config_key())
+            .ok_or_else(|| zbus::fdo::Error::Failed(format!(
+                "No config entry for profile '{}'", profile_enum.config_key()
+            )))?;
+
+        if let Err(e) = self.cpu.apply_profile(profile_enum.config_key(), settings) {
             error!("Failed to apply performance profile: {}", e);
             return Err(zbus::fdo::Error::Failed(format!("CPU control error: {}", e)));
         }
 
         *self.current_profile.lock().await = Some(profile_enum);
+        *self.current_variant.lock().await = None;
 
         Ok(format!("Performance profile set to: {}", profile))
     }
 
     async fn get_current_profile(&self) -> String {
-        if let Some(profile) = *self.current_profile.lock().await {
-            format!("{:?}", profile)
-        } else {
-            "Unknown".to_string()
+        if let Some(name) = &*self.current_variant.lock().await {
+            return format!("Variant: {}", name);
+        }
+        match *self.current_profile.lock().await {
+            Some(profile) => format!("{:?}", profile),
+            None => "Unknown".to_string(),
         }
     }
 
-    async fn get_cpu_status(&self) -> zbus::fdo::Result<(String, String)> {
-        match (self.cpu.get_current_governor(), self.cpu.get_current_epp()) {
-            (Ok(gov), Ok(epp)) => Ok((gov, epp)),
-            (Err(e), _) | (_, Err(e)) => {
-                error!("Failed to read CPU status: {}", e);
-                Err(zbus::fdo::Error::Failed(format!("CPU read error: {}", e)))
-            }
+    /// Persists the governor/EPP/turbo/power-limit values currently in effect
+    /// under `name`, so they can later be restored with `load_variant`.
+    /// Captures live values (rather than a reference to the active profile
+    /// name) so a manual `set_power_limits` override applied on top of a
+    /// profile is preserved. Saving over the name `"default"` makes it the
+    /// variant auto-applied on the next startup.
+    async fn save_variant(&self, name: &str) -> zbus::fdo::Result<String> {
+        let governor = self.cpu.get_current_governor().map_err(|e| {
+            zbus::fdo::Error::Failed(format!("Failed to read governor: {}", e))
+        })?;
+        let epp = self.cpu.get_current_epp().map_err(|e| {
+            zbus::fdo::Error::Failed(format!("Failed to read EPP: {}", e))
+        })?;
+
+        let turbo = self.cpu.get_turbo_enabled().map_err(|e| {
+            zbus::fdo::Error::Failed(format!("Failed to read turbo state: {}", e))
+        })?;
+        let (pl1_watts, pl2_watts) = match self.cpu.get_power_limits() {
+            Ok((pl1, pl2)) => (Some(pl1), Some(pl2)),
+            Err(_) => (None, None),
+        };
+
+        let mut store = self.variants.lock().await;
+        store.variants.insert(
+            name.to_string(),
+            PerformanceVariant { governor, epp, turbo, pl1_watts, pl2_watts },
+        );
+        store
+            .save(DEFAULT_VARIANTS_PATH)
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to save variant: {}", e)))?;
+
+        Ok(format!("Saved variant '{}'", name))
+    }
+
+    /// Re-applies the governor/EPP/turbo/power-limit values previously saved
+    /// as `name`.
+    async fn load_variant(&self, name: &str) -> zbus::fdo::Result<String> {
+        let variant = {
+            let store = self.variants.lock().await;
+            store
+                .variants
+                .get(name)
+                .cloned()
+                .ok_or_else(|| zbus::fdo::Error::Failed(format!("No such variant: {}", name)))?
+        };
+
+        let settings = ProfileSettings {
+            governor: variant.governor,
+            epp: variant.epp,
+            turbo: variant.turbo,
+            pl1_watts: variant.pl1_watts,
+            pl2_watts: variant.pl2_watts,
+            pl1_time_window_us: None,
+            pl2_time_window_us: None,
+        };
+
+        if let Err(e) = self.cpu.apply_profile(&format!("variant:{}", name), &settings) {
+            error!("Failed to apply variant '{}': {}", name, e);
+            return Err(zbus::fdo::Error::Failed(format!("CPU control error: {}", e)));
         }
+
+        *self.current_profile.lock().await = None;
+        *self.current_variant.lock().await = Some(name.to_string());
+
+        Ok(format!("Loaded variant '{}'", name))
+    }
+
+    /// Lists the names of all persisted variants.
+    async fn list_variants(&self) -> Vec<String> {
+        self.variants.lock().await.variants.keys().cloned().collect()
+    }
+
+    /// Returns the last sampled governor/EPP rather than reading sysfs again;
+    /// see `run_telemetry_sampler` for how the cache is kept fresh.
+    async fn get_cpu_status(&self) -> zbus::fdo::Result<(String, String)> {
+        let t = self.telemetry.lock().await;
+        Ok((t.governor.clone(), t.epp.clone()))
+    }
+
+    /// Sets PL1 (sustained) and PL2 (burst) power limits in watts via Intel
+    /// RAPL, clamped to the hardware-reported max. Returns the limits
+    /// actually applied.
+    async fn set_power_limits(&self, pl1_watts: f64, pl2_watts: f64) -> zbus::fdo::Result<(f64, f64)> {
+        self.cpu.set_power_limits(pl1_watts, pl2_watts).map_err(|e| {
+            error!("Failed to set power limits: {}", e);
+            zbus::fdo::Error::Failed(format!("RAPL error: {}", e))
+        })
+    }
+
+    async fn get_power_limits(&self) -> zbus::fdo::Result<(f64, f64)> {
+        self.cpu.get_power_limits().map_err(|e| {
+            error!("Failed to read power limits: {}", e);
+            zbus::fdo::Error::Failed(format!("RAPL error: {}", e))
+        })
     }
+
+    /// Emitted on each telemetry sample; carries the same readings as
+    /// `get_cpu_status`, so clients can subscribe instead of polling.
+    #[zbus(signal)]
+    async fn telemetry_updated(
+        emitter: &SignalEmitter<'_>,
+        governor: String,
+        epp: String,
+    ) -> zbus::Result<()>;
 }
 
 #[tokio::main]
@@ -264,18 +303,38 @@ async fn main() -> Result<()> {
     info!("Version: 1.0.0");
     info!("Project: https://github.com/yourrepo/boreas");
 
-    // CRITICAL: Verify hardware compatibility before proceeding
-    verify_hardware()?;
+    let config = Arc::new(Config::load(config::DEFAULT_CONFIG_PATH)?);
+    let dev_mode = dev_mode_requested();
 
-    let cpu = Arc::new(CpuController::new()?);
+    let cpu: Arc<dyn CpuBackend> = if dev_mode {
+        warn!("Running in --dev mode: hardware safety check skipped, CPU writes are logged only");
+        Arc::new(DevCpuBackend::new())
+    } else {
+        // CRITICAL: Verify hardware compatibility before proceeding
+        verify_hardware(&config)?;
+        Arc::new(RealCpuBackend::new()?)
+    };
+
+    let telemetry = Arc::new(Mutex::new(Telemetry::default()));
+    // Warm the cache with a live read before the service goes live on D-Bus,
+    // so `get_cpu_status` never hands out an empty placeholder to an early caller.
+    if let Some(sample) = sample_telemetry(&cpu) {
+        *telemetry.lock().await = sample;
+    }
+    let variant_store = Arc::new(Mutex::new(VariantStore::load(DEFAULT_VARIANTS_PATH)?));
+    let has_default_variant = variant_store.lock().await.variants.contains_key(DEFAULT_VARIANT_NAME);
 
     let service = PrometheusService {
         cpu: cpu.clone(),
+        config: config.clone(),
         current_profile: Arc::new(Mutex::new(None)),
+        telemetry: telemetry.clone(),
+        variants: variant_store,
+        current_variant: Arc::new(Mutex::new(None)),
     };
 
     info!("Connecting to system D-Bus...");
-    let _conn = ConnectionBuilder::system()?
+    let conn = ConnectionBuilder::system()?
         .name("org.jesternet.Prometheus")?
         .serve_at("/org/jesternet/Prometheus", service)?
         .build()
@@ -284,6 +343,25 @@ async fn main() -> Result<()> {
     info!("Prometheus daemon ready on D-Bus: org.jesternet.Prometheus");
     info!("Available profiles: silent, balanced, warspeed");
 
+    let iface_ref = conn
+        .object_server()
+        .interface::<_, PrometheusService>("/org/jesternet/Prometheus")
+        .await?;
+    tokio::spawn(run_telemetry_sampler(
+        cpu,
+        telemetry,
+        iface_ref.signal_emitter().to_owned(),
+        config.telemetry.interval(),
+    ));
+
+    if has_default_variant {
+        info!("Restoring '{}' performance variant from previous session", DEFAULT_VARIANT_NAME);
+        let iface = iface_ref.get().await;
+        if let Err(e) = iface.load_variant(DEFAULT_VARIANT_NAME).await {
+            warn!("Failed to restore default performance variant: {}", e);
+        }
+    }
+
     // Keep running
     std::future::pending::<()>().await;
 
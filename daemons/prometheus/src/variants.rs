@@ -0,0 +1,72 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use tracing::info;
+
+/// Default location for persisted performance variants.
+pub const DEFAULT_VARIANTS_PATH: &str = "/var/lib/prometheus/variants.toml";
+
+/// Name of the variant auto-applied at startup, if one has been saved.
+pub const DEFAULT_VARIANT_NAME: &str = "default";
+
+/// A named, persisted performance configuration. Captures the
+/// governor/EPP/turbo/power-limit values actually in effect at save time,
+/// rather than a reference to a `[profiles.<name>]` entry — otherwise a
+/// manual override applied after setting a profile (e.g. a one-off
+/// `set_power_limits` call) would be silently discarded on the next
+/// `load_variant`, since reloading the profile name alone re-applies only its
+/// config defaults.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PerformanceVariant {
+    pub governor: String,
+    pub epp: String,
+    pub turbo: bool,
+    /// `None` when RAPL wasn't available to read at save time; `load_variant`
+    /// then falls back to the hardware-reported package max, same as a
+    /// profile's `pl1_watts`/`pl2_watts` of `None`.
+    #[serde(default)]
+    pub pl1_watts: Option<f64>,
+    #[serde(default)]
+    pub pl2_watts: Option<f64>,
+}
+
+/// On-disk store of named performance variants, keyed by variant name.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VariantStore {
+    pub variants: HashMap<String, PerformanceVariant>,
+}
+
+impl VariantStore {
+    /// Loads variants from `path`, falling back to an empty store when the
+    /// file does not exist. A present-but-malformed file is a startup error.
+    pub fn load(path: &str) -> Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(contents) => {
+                info!("Loading performance variants from {}", path);
+                toml::from_str(&contents)
+                    .with_context(|| format!("Failed to parse variants file {}", path))
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                info!("No variants file at {}, starting with none saved", path);
+                Ok(Self::default())
+            }
+            Err(e) => Err(e).with_context(|| format!("Failed to read variants file {}", path)),
+        }
+    }
+
+    /// Serializes and writes the store to `path`, creating its parent
+    /// directory if necessary.
+    pub fn save(&self, path: &str) -> Result<()> {
+        if let Some(parent) = Path::new(path).parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory for {}", path))?;
+        }
+        let contents = toml::to_string_pretty(self).context("Failed to serialize performance variants")?;
+        fs::write(path, contents).with_context(|| format!("Failed to write variants file {}", path))?;
+        info!("Saved performance variants to {}", path);
+        Ok(())
+    }
+}